@@ -0,0 +1,21 @@
+use crate::Id;
+
+/// A change to a row in a `Table`, delivered to subscribers of
+/// [`crate::Table::subscribe`].
+#[derive(Debug)]
+pub enum RowChange<T> {
+    /// A new row was inserted
+    Inserted(Id<T>),
+    /// An existing row was mutated
+    Updated(Id<T>),
+    /// A row was removed
+    Removed(Id<T>),
+}
+
+impl<T> Clone for RowChange<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for RowChange<T> {}
@@ -0,0 +1,117 @@
+//! Type-erased trait queries on row data.
+//!
+//! Lets callers ask a `Row<T>`/`RowMut<T>` for a `&dyn Trait` without
+//! knowing `T`, as long as `T`'s implementation of `Trait` was registered
+//! with the [`interfaces!`] macro.
+
+use std::any::{Any, TypeId};
+
+use hashbrown::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// The casting functions registered for one `(T, dyn Trait)` pair.
+struct Caster<T, D: ?Sized> {
+    cast_ref: fn(&T) -> &D,
+    cast_mut: fn(&mut T) -> &mut D,
+}
+
+/// Registered casters, keyed by `(TypeId::of::<T>(), TypeId::of::<D>())`.
+type Registry = Mutex<HashMap<(TypeId, TypeId), Box<dyn Any + Send + Sync>>>;
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Register the `&T as &D` / `&mut T as &mut D` casts for a concrete type,
+/// keyed by `(TypeId::of::<T>(), TypeId::of::<D>())`. Called by
+/// [`interfaces!`], not normally by hand.
+pub fn register<T, D>(cast_ref: fn(&T) -> &D, cast_mut: fn(&mut T) -> &mut D)
+where
+    T: 'static,
+    D: ?Sized + 'static,
+{
+    let key = (TypeId::of::<T>(), TypeId::of::<D>());
+    let caster: Caster<T, D> = Caster { cast_ref, cast_mut };
+    registry().lock().unwrap().insert(key, Box::new(caster));
+}
+
+/// Look up a shared reference to `data` as `&D`, if `T: D` was registered.
+pub fn query<T, D>(data: &T) -> Option<&D>
+where
+    T: 'static,
+    D: ?Sized + 'static,
+{
+    let key = (TypeId::of::<T>(), TypeId::of::<D>());
+    let guard = registry().lock().unwrap();
+    let cast_ref = guard.get(&key)?.downcast_ref::<Caster<T, D>>()?.cast_ref;
+    drop(guard);
+    Some(cast_ref(data))
+}
+
+/// Look up a mutable reference to `data` as `&mut D`, if `T: D` was registered.
+pub fn query_mut<T, D>(data: &mut T) -> Option<&mut D>
+where
+    T: 'static,
+    D: ?Sized + 'static,
+{
+    let key = (TypeId::of::<T>(), TypeId::of::<D>());
+    let guard = registry().lock().unwrap();
+    let cast_mut = guard.get(&key)?.downcast_ref::<Caster<T, D>>()?.cast_mut;
+    drop(guard);
+    Some(cast_mut(data))
+}
+
+/// Register a concrete type's trait implementations for [`query`]/[`query_mut`].
+///
+/// ```ignore
+/// interfaces!(MyType: Display, Serialize);
+/// ```
+#[macro_export]
+macro_rules! interfaces {
+    ($ty:ty : $($trait_:path),+ $(,)?) => {
+        $(
+            $crate::interfaces::register::<$ty, dyn $trait_>(
+                |data: &$ty| data as &dyn $trait_,
+                |data: &mut $ty| data as &mut dyn $trait_,
+            );
+        )+
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt;
+
+    struct Greeter(&'static str);
+
+    impl fmt::Display for Greeter {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    /// Never registered with `interfaces!`, so every query for it must miss.
+    struct Unregistered;
+
+    #[test]
+    fn query_hits_registered_trait() {
+        crate::interfaces!(Greeter: std::fmt::Display);
+        let greeter = Greeter("hi");
+        let displayed = query::<Greeter, dyn fmt::Display>(&greeter).unwrap();
+        assert_eq!(displayed.to_string(), "hi");
+    }
+
+    #[test]
+    fn query_miss_returns_none_rather_than_panicking() {
+        let unregistered = Unregistered;
+        assert!(query::<Unregistered, dyn fmt::Display>(&unregistered).is_none());
+    }
+
+    #[test]
+    fn query_mut_miss_returns_none_rather_than_panicking() {
+        let mut unregistered = Unregistered;
+        assert!(query_mut::<Unregistered, dyn fmt::Display>(&mut unregistered).is_none());
+    }
+}
@@ -0,0 +1,10 @@
+mod change;
+mod id;
+pub mod interfaces;
+mod row;
+mod table;
+
+pub use change::RowChange;
+pub use id::Id;
+pub use row::{MappedRow, OrderedRowIter, OrderedRowIterMut, Row, RowIter, RowIterMut, RowMut, SortedRowIter};
+pub use table::Table;
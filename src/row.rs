@@ -5,12 +5,16 @@ use std::{
 
 use hashbrown::hash_map;
 
-use crate::Id;
+use crate::{
+    change::RowChange,
+    table::{broadcast, Subscribers},
+    Id,
+};
 
 /// A row in a `Table`
 pub struct Row<'a, T> {
-    id: Id<T>,
-    data: &'a T,
+    pub(crate) id: Id<T>,
+    pub(crate) data: &'a T,
 }
 
 impl<'a, T> Row<'a, T> {
@@ -26,6 +30,22 @@ impl<'a, T> Row<'a, T> {
     }
 }
 
+impl<'a, T> Row<'a, T>
+where
+    T: 'static,
+{
+    /// Query the row's data for a registered trait implementation, without
+    /// the caller needing to know the concrete type `T`. Returns `None` if
+    /// `T`'s implementation of `Dyn` was never registered via
+    /// [`crate::interfaces!`].
+    pub fn query<Dyn>(&self) -> Option<&Dyn>
+    where
+        Dyn: ?Sized + 'static,
+    {
+        crate::interfaces::query(self.data)
+    }
+}
+
 impl<'a, T> Clone for Row<'a, T> {
     fn clone(&self) -> Self {
         Row {
@@ -102,9 +122,16 @@ where
 }
 
 /// A mutable row in a `Table`
+///
+/// Dropping a `RowMut` notifies the table's subscribers with
+/// [`RowChange::Updated`], but only if the row was actually mutated through
+/// [`DerefMut`]/[`AsMut`]/[`RowMut::query_mut`] — reading through a `RowMut`
+/// never emits an event.
 pub struct RowMut<'a, T> {
-    id: Id<T>,
-    data: &'a mut T,
+    pub(crate) id: Id<T>,
+    pub(crate) data: &'a mut T,
+    pub(crate) dirty: bool,
+    pub(crate) subscribers: &'a Subscribers<T>,
 }
 
 impl<'a, T> RowMut<'a, T> {
@@ -120,6 +147,40 @@ impl<'a, T> RowMut<'a, T> {
     }
 }
 
+impl<'a, T> Drop for RowMut<'a, T> {
+    fn drop(&mut self) {
+        if self.dirty {
+            broadcast(self.subscribers, RowChange::Updated(self.id));
+        }
+    }
+}
+
+impl<'a, T> RowMut<'a, T>
+where
+    T: 'static,
+{
+    /// Query the row's data for a registered trait implementation, without
+    /// the caller needing to know the concrete type `T`. See
+    /// [`Row::query`].
+    pub fn query<Dyn>(&self) -> Option<&Dyn>
+    where
+        Dyn: ?Sized + 'static,
+    {
+        crate::interfaces::query(self.data)
+    }
+
+    /// Mutable counterpart to [`Self::query`]. A successful query marks the
+    /// row as mutated, like [`DerefMut`].
+    pub fn query_mut<Dyn>(&mut self) -> Option<&mut Dyn>
+    where
+        Dyn: ?Sized + 'static,
+    {
+        let data = crate::interfaces::query_mut(&mut *self.data);
+        self.dirty |= data.is_some();
+        data
+    }
+}
+
 impl<'a, T, U> PartialEq<RowMut<'a, U>> for RowMut<'a, T>
 where
     T: PartialEq<U>,
@@ -158,6 +219,7 @@ impl<'a, T> Deref for RowMut<'a, T> {
 
 impl<'a, T> DerefMut for RowMut<'a, T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
+        self.dirty = true;
         self.data
     }
 }
@@ -170,6 +232,7 @@ impl<'a, T> AsRef<T> for RowMut<'a, T> {
 
 impl<'a, T> AsMut<T> for RowMut<'a, T> {
     fn as_mut(&mut self) -> &mut T {
+        self.dirty = true;
         self.data
     }
 }
@@ -198,8 +261,8 @@ where
 
 /// A row that was mapped from another and owns its data
 pub struct MappedRow<I, T> {
-    id: Id<I>,
-    data: T,
+    pub(crate) id: Id<I>,
+    pub(crate) data: T,
 }
 
 impl<I, T> MappedRow<I, T> {
@@ -215,6 +278,29 @@ impl<I, T> MappedRow<I, T> {
     }
 }
 
+impl<I, T> MappedRow<I, T>
+where
+    T: 'static,
+{
+    /// Query the row's data for a registered trait implementation, without
+    /// the caller needing to know the concrete type `T`. See
+    /// [`Row::query`].
+    pub fn query<Dyn>(&self) -> Option<&Dyn>
+    where
+        Dyn: ?Sized + 'static,
+    {
+        crate::interfaces::query(&self.data)
+    }
+
+    /// Mutable counterpart to [`Self::query`].
+    pub fn query_mut<Dyn>(&mut self) -> Option<&mut Dyn>
+    where
+        Dyn: ?Sized + 'static,
+    {
+        crate::interfaces::query_mut(&mut self.data)
+    }
+}
+
 impl<I, T> Clone for MappedRow<I, T>
 where
     T: Clone,
@@ -328,11 +414,94 @@ impl<'a, T> Clone for RowIter<'a, T> {
 #[derive(Debug)]
 pub struct RowIterMut<'a, T> {
     pub(crate) inner: hash_map::IterMut<'a, Id<T>, T>,
+    pub(crate) subscribers: &'a Subscribers<T>,
 }
 
 impl<'a, T> Iterator for RowIterMut<'a, T> {
     type Item = RowMut<'a, T>;
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|(id, data)| RowMut { id: *id, data })
+        self.inner.next().map(|(id, data)| RowMut {
+            id: *id,
+            data,
+            dirty: false,
+            subscribers: self.subscribers,
+        })
+    }
+}
+
+/// An iterator over rows in a `Table`, sorted by `Id`
+#[derive(Debug)]
+pub struct OrderedRowIter<'a, T> {
+    pub(crate) inner: std::vec::IntoIter<Row<'a, T>>,
+}
+
+impl<'a, T> Iterator for OrderedRowIter<'a, T> {
+    type Item = Row<'a, T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+/// A mutable iterator over rows in a `Table`, sorted by `Id`
+#[derive(Debug)]
+pub struct OrderedRowIterMut<'a, T> {
+    pub(crate) inner: std::vec::IntoIter<RowMut<'a, T>>,
+}
+
+impl<'a, T> Iterator for OrderedRowIterMut<'a, T> {
+    type Item = RowMut<'a, T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+/// An iterator over rows in a `Table`, sorted by a comparator over their data
+#[derive(Debug)]
+pub struct SortedRowIter<'a, T> {
+    pub(crate) inner: std::vec::IntoIter<Row<'a, T>>,
+}
+
+impl<'a, T> Iterator for SortedRowIter<'a, T> {
+    type Item = Row<'a, T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Table;
+
+    #[test]
+    fn read_only_deref_does_not_emit_update() {
+        let mut table = Table::new();
+        let id = table.insert(1);
+        let rx = table.subscribe();
+        let row = table.get_mut(id).unwrap();
+        let _ = *row;
+        drop(row);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn deref_mut_emits_update_on_drop() {
+        let mut table = Table::new();
+        let id = table.insert(1);
+        let rx = table.subscribe();
+        let mut row = table.get_mut(id).unwrap();
+        *row = 2;
+        drop(row);
+        assert!(matches!(rx.try_recv().unwrap(), crate::RowChange::Updated(i) if i == id));
+    }
+
+    #[test]
+    fn as_mut_emits_update_on_drop() {
+        let mut table = Table::new();
+        let id = table.insert(1);
+        let rx = table.subscribe();
+        let mut row = table.get_mut(id).unwrap();
+        *row.as_mut() = 2;
+        drop(row);
+        assert!(matches!(rx.try_recv().unwrap(), crate::RowChange::Updated(i) if i == id));
     }
 }
@@ -0,0 +1,269 @@
+use std::{
+    cmp::Ordering,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Mutex,
+    },
+};
+
+use hashbrown::HashMap;
+
+use crate::{
+    change::RowChange,
+    id::Id,
+    row::{MappedRow, OrderedRowIter, OrderedRowIterMut, Row, RowIter, RowIterMut, RowMut, SortedRowIter},
+};
+
+/// The subscribers of a `Table`, shared between the table itself and any
+/// outstanding `RowMut` so both can broadcast [`RowChange`]s. A `Mutex`
+/// rather than a `RefCell` so `Table<T>` stays `Sync` for `Send + Sync` `T`.
+pub(crate) type Subscribers<T> = Mutex<Vec<Sender<RowChange<T>>>>;
+
+/// Broadcast `change` to every subscriber, pruning any whose receiver was dropped.
+pub(crate) fn broadcast<T>(subscribers: &Subscribers<T>, change: RowChange<T>) {
+    subscribers.lock().unwrap().retain(|tx| tx.send(change).is_ok());
+}
+
+/// A collection of rows of type `T`, each addressable by a unique `Id`
+#[derive(Debug, Default)]
+pub struct Table<T> {
+    rows: HashMap<Id<T>, T>,
+    next_id: u64,
+    subscribers: Subscribers<T>,
+}
+
+impl<T> Table<T> {
+    /// Create an empty table
+    pub fn new() -> Self {
+        Self {
+            rows: HashMap::new(),
+            next_id: 0,
+            subscribers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Subscribe to this table's row changes. The receiver gets an
+    /// [`RowChange`] for every subsequent insert, mutation, and removal.
+    pub fn subscribe(&self) -> Receiver<RowChange<T>> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.lock().unwrap().push(tx);
+        rx
+    }
+
+    /// Insert a new row, returning its `Id`
+    pub fn insert(&mut self, data: T) -> Id<T> {
+        let id = Id::new(self.next_id);
+        self.next_id += 1;
+        self.rows.insert(id, data);
+        broadcast(&self.subscribers, RowChange::Inserted(id));
+        id
+    }
+
+    /// Remove the row with the given `Id`
+    pub fn remove(&mut self, id: Id<T>) -> Option<T> {
+        let removed = self.rows.remove(&id);
+        if removed.is_some() {
+            broadcast(&self.subscribers, RowChange::Removed(id));
+        }
+        removed
+    }
+
+    /// Get a row by its `Id`
+    pub fn get(&self, id: Id<T>) -> Option<Row<'_, T>> {
+        self.rows.get(&id).map(|data| Row { id, data })
+    }
+
+    /// Get a mutable row by its `Id`
+    pub fn get_mut(&mut self, id: Id<T>) -> Option<RowMut<'_, T>> {
+        let Table { rows, subscribers, .. } = self;
+        rows.get_mut(&id).map(|data| RowMut {
+            id,
+            data,
+            dirty: false,
+            subscribers,
+        })
+    }
+
+    /// Iterate over all rows in unspecified order
+    pub fn rows(&self) -> RowIter<'_, T> {
+        RowIter {
+            inner: self.rows.iter(),
+        }
+    }
+
+    /// Mutably iterate over all rows in unspecified order
+    pub fn rows_mut(&mut self) -> RowIterMut<'_, T> {
+        let Table { rows, subscribers, .. } = self;
+        RowIterMut {
+            inner: rows.iter_mut(),
+            subscribers,
+        }
+    }
+
+    /// Iterate over all rows sorted by `Id`
+    pub fn rows_ordered(&self) -> OrderedRowIter<'_, T> {
+        let mut rows: Vec<_> = self.rows.iter().map(|(id, data)| Row { id: *id, data }).collect();
+        rows.sort_by_key(|row| row.id);
+        OrderedRowIter {
+            inner: rows.into_iter(),
+        }
+    }
+
+    /// Mutably iterate over all rows sorted by `Id`
+    pub fn rows_ordered_mut(&mut self) -> OrderedRowIterMut<'_, T> {
+        let Table { rows, subscribers, .. } = self;
+        let mut rows: Vec<_> = rows
+            .iter_mut()
+            .map(|(id, data)| RowMut {
+                id: *id,
+                data,
+                dirty: false,
+                subscribers,
+            })
+            .collect();
+        rows.sort_by_key(|row| row.id);
+        OrderedRowIterMut {
+            inner: rows.into_iter(),
+        }
+    }
+
+    /// Iterate over all rows sorted by a comparator over their data
+    pub fn rows_sorted_by<F>(&self, mut cmp: F) -> SortedRowIter<'_, T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut rows: Vec<_> = self.rows.iter().map(|(id, data)| Row { id: *id, data }).collect();
+        rows.sort_by(|a, b| cmp(a.data, b.data));
+        SortedRowIter {
+            inner: rows.into_iter(),
+        }
+    }
+
+    /// Pull the first `n` rows in comparator order without fully sorting the table
+    pub fn top_k<F>(&self, n: usize, mut cmp: F) -> Vec<MappedRow<T, T>>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        T: Clone,
+    {
+        let mut rows: Vec<_> = self.rows.iter().map(|(id, data)| (*id, data)).collect();
+        let k = n.min(rows.len());
+        if k > 0 && k < rows.len() {
+            rows.select_nth_unstable_by(k - 1, |(_, a), (_, b)| cmp(a, b));
+        }
+        rows.truncate(k);
+        rows.sort_by(|(_, a), (_, b)| cmp(a, b));
+        rows.into_iter()
+            .map(|(id, data)| MappedRow { id, data: data.clone() })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deliberately not `Clone`, so `rows_ordered` compiling against it
+    /// demonstrates it doesn't require cloning `T`.
+    #[derive(Debug, PartialEq)]
+    struct NoClone(i32);
+
+    #[test]
+    fn rows_ordered_is_sorted_by_id() {
+        let mut table = Table::new();
+        let ids: Vec<_> = (0..5).map(|n| table.insert(NoClone(n))).collect();
+        let ordered: Vec<_> = table.rows_ordered().map(|row| row.id).collect();
+        let mut expected = ids;
+        expected.sort();
+        assert_eq!(ordered, expected);
+    }
+
+    #[test]
+    fn rows_sorted_by_orders_by_comparator() {
+        let mut table = Table::new();
+        for n in [3, 1, 4, 1, 5] {
+            table.insert(n);
+        }
+        let sorted: Vec<i32> = table.rows_sorted_by(|a, b| a.cmp(b)).map(|row| *row).collect();
+        assert_eq!(sorted, vec![1, 1, 3, 4, 5]);
+    }
+
+    #[test]
+    fn top_k_matches_prefix_of_full_sort() {
+        let mut table = Table::new();
+        for n in [9, 2, 7, 1, 5, 3, 8] {
+            table.insert(n);
+        }
+        let top3 = table.top_k(3, |a, b| a.cmp(b));
+        let top3: Vec<i32> = top3.into_iter().map(|row| *row).collect();
+        assert_eq!(top3, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn top_k_saturates_at_table_len() {
+        let mut table = Table::new();
+        table.insert(1);
+        table.insert(2);
+        let top = table.top_k(10, |a, b| a.cmp(b));
+        assert_eq!(top.len(), 2);
+    }
+
+    #[test]
+    fn rows_mut_visits_every_row_and_mutates_in_place() {
+        let mut table = Table::new();
+        for n in [1, 2, 3] {
+            table.insert(n);
+        }
+        for mut row in table.rows_mut() {
+            *row *= 10;
+        }
+        let mut values: Vec<i32> = table.rows().map(|row| *row).collect();
+        values.sort();
+        assert_eq!(values, vec![10, 20, 30]);
+    }
+
+    #[test]
+    fn rows_ordered_mut_is_sorted_by_id() {
+        let mut table = Table::new();
+        let ids: Vec<_> = (0..5).map(|n| table.insert(n)).collect();
+        let ordered: Vec<_> = table.rows_ordered_mut().map(|row| row.id).collect();
+        let mut expected = ids;
+        expected.sort();
+        assert_eq!(ordered, expected);
+    }
+
+    #[test]
+    fn rows_ordered_mut_broadcasts_updated_only_for_mutated_rows() {
+        let mut table = Table::new();
+        let id_a = table.insert(1);
+        let id_b = table.insert(2);
+        let rx = table.subscribe();
+        for mut row in table.rows_ordered_mut() {
+            if row.id == id_a {
+                *row = 100;
+            }
+        }
+        assert!(matches!(rx.recv().unwrap(), RowChange::Updated(i) if i == id_a));
+        assert!(rx.try_recv().is_err());
+        assert_eq!(*table.get(id_a).unwrap(), 100);
+        assert_eq!(*table.get(id_b).unwrap(), 2);
+    }
+
+    #[test]
+    fn insert_and_remove_broadcast_changes() {
+        let mut table = Table::new();
+        let rx = table.subscribe();
+        let id = table.insert("a");
+        table.remove(id);
+        assert!(matches!(rx.recv().unwrap(), RowChange::Inserted(i) if i == id));
+        assert!(matches!(rx.recv().unwrap(), RowChange::Removed(i) if i == id));
+    }
+
+    #[test]
+    fn dropped_subscriber_is_pruned_on_next_broadcast() {
+        let mut table = Table::new();
+        let rx = table.subscribe();
+        drop(rx);
+        table.insert("a");
+        assert!(table.subscribers.lock().unwrap().is_empty());
+    }
+}